@@ -0,0 +1,170 @@
+//! What skim asks the picker's entries for: their text and display on every
+//! keystroke and render, and previews while scrolling back and forth over the
+//! same entries. Run with `cargo bench`
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use clap::Parser;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nu_history_skim::{
+    search_history, Args, HistoryItemSkim, HistorySearch, Location, PREVIEW_CACHE,
+};
+use reedline::{HistoryItem, SqliteBackedHistory};
+use rusqlite::Connection;
+use skim::{DisplayContext, Matches, PreviewContext, SkimItem};
+use tuikit::attr::Attr;
+
+/// Args like the command line with `flags` would give them
+fn args(flags: &[&str]) -> Arc<Args> {
+    let flags = std::iter::once("nu-history-skim").chain(flags.iter().copied());
+    Arc::new(Args::parse_from(flags))
+}
+
+/// A history of 10k commands with dates and durations, as the picker wraps it
+fn entries(flags: &[&str]) -> Vec<HistoryItemSkim> {
+    let args = args(flags);
+    (0..10_000)
+        .map(|i| {
+            let mut item = HistoryItem::from_command_line(format!("cargo build --bin tool-{i}"));
+            item.start_timestamp = Some(Utc.timestamp(1_700_000_000 + i * 60, 0));
+            item.duration = Some(Duration::from_millis(1500));
+            HistoryItemSkim::new(item, args.clone())
+        })
+        .collect()
+}
+
+/// A database of 100k commands in sessions of 100, removed again when dropped
+struct BenchHistory(PathBuf);
+
+impl BenchHistory {
+    fn new() -> BenchHistory {
+        let path = std::env::temp_dir().join(format!(
+            "nu-history-skim-bench-{}.sqlite3",
+            std::process::id()
+        ));
+        let history = BenchHistory(path);
+        history.remove();
+        drop(SqliteBackedHistory::with_file(history.0.clone()).unwrap());
+        let mut conn = Connection::open(&history.0).unwrap();
+        let tx = conn.transaction().unwrap();
+        for i in 0..100_000_i64 {
+            tx.execute(
+                "insert into history(command_line, start_timestamp, session_id, hostname, cwd)
+                values (?, ?, ?, 'bench', '/home/bench')",
+                rusqlite::params![format!("cargo build --bin tool-{i}"), i * 60_000, i / 100],
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+        history
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    fn remove(&self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut path = self.0.clone().into_os_string();
+            path.push(suffix);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+impl Drop for BenchHistory {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+fn match_text(c: &mut Criterion) {
+    let entries = entries(&["--match-scope", "full", "--timezone", "UTC"]);
+    let mut group = c.benchmark_group("text of 10k entries with --match-scope full");
+    group.bench_function("formatted when loaded", |b| {
+        b.iter(|| {
+            entries
+                .iter()
+                .map(|e| black_box(e.text()).len())
+                .sum::<usize>()
+        })
+    });
+    // how `text()` built it before it was kept in the entry
+    group.bench_function("formatted on every call", |b| {
+        b.iter(|| {
+            entries
+                .iter()
+                .map(|e| black_box(format!("{}{}", e.line_prefix(false), e.command())).len())
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+fn display(c: &mut Criterion) {
+    let entries = entries(&["--timezone", "UTC"]);
+    c.bench_function("display a screen of 50 entries", |b| {
+        b.iter(|| {
+            for entry in &entries[..50] {
+                let text = entry.text();
+                let context = DisplayContext {
+                    text: &text,
+                    score: 0,
+                    matches: Matches::None,
+                    container_width: 120,
+                    highlight_attr: Attr::default(),
+                };
+                black_box(entry.display(context));
+            }
+        })
+    });
+}
+
+fn scroll(c: &mut Criterion) {
+    let history = BenchHistory::new();
+    let file = history.path().to_str().unwrap();
+    let args = args(&["--history-file", file, "--context", "3"]);
+    let all = HistorySearch::Query("");
+    let entries: Vec<_> = search_history(&args, Location::Everywhere, all, Some(30))
+        .into_iter()
+        .map(|e| HistoryItemSkim::new(e, args.clone()))
+        .collect();
+    // down the 30 newest entries and back up again, five times
+    let order: Vec<&HistoryItemSkim> = (0..5)
+        .flat_map(|_| entries.iter().chain(entries.iter().rev()))
+        .collect();
+    let preview = |entry: &HistoryItemSkim| {
+        let context = PreviewContext {
+            query: "",
+            cmd_query: "",
+            width: 80,
+            height: 40,
+            current_index: 0,
+            current_selection: "",
+            selected_indices: &[],
+            selections: &[],
+        };
+        black_box(entry.preview(context));
+    };
+    let mut group = c.benchmark_group("scroll over 30 entries five times with --context 3");
+    group.sample_size(20);
+    group.bench_function("cached", |b| {
+        b.iter(|| order.iter().for_each(|e| preview(e)))
+    });
+    // what every preview cost before the cache
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            for entry in &order {
+                PREVIEW_CACHE.lock().unwrap().clear();
+                preview(entry);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, match_text, display, scroll);
+criterion_main!(benches);